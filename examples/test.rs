@@ -1,9 +1,58 @@
 // Rust Test File
+#![cfg_attr(not(feature = "std"), no_std)]
+// This file doubles as a language feature tour, so many items are intentionally
+// unused; silence the resulting noise rather than sprinkling per-item allows.
+#![allow(dead_code)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, format, string::String, string::ToString, vec, vec::Vec};
+
+// Collections: std on the happy path, `hashbrown` when `std` is off.
+#[cfg(feature = "std")]
 use std::collections::HashMap;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+
+// `Display` is always available; the `std::error::Error` impl is std-only.
+use core::fmt;
+use core::num::{ParseFloatError, ParseIntError};
+use core::str::FromStr;
+
+use chrono::NaiveDateTime;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt;
+
+// Shared ownership + interior mutability. On std we use the standard
+// `Arc`/`Mutex`; on no_std we use `alloc`'s `Arc` plus a spin-lock wrapper that
+// keeps the `lock().unwrap()` call shape identical.
+#[cfg(feature = "std")]
 use std::sync::{Arc, Mutex};
 
+#[cfg(not(feature = "std"))]
+use nostd_sync::{Arc, Mutex};
+
+#[cfg(not(feature = "std"))]
+mod nostd_sync {
+    pub use alloc::sync::Arc;
+
+    // Thin wrapper over `spin::Mutex` presenting the same `lock() -> Result`
+    // surface as `std::sync::Mutex`, so call sites are identical across builds.
+    pub struct Mutex<T>(spin::Mutex<T>);
+
+    impl<T> Mutex<T> {
+        pub fn new(value: T) -> Self {
+            Mutex(spin::Mutex::new(value))
+        }
+
+        pub fn lock(&self) -> Result<spin::MutexGuard<'_, T>, core::convert::Infallible> {
+            Ok(self.0.lock())
+        }
+    }
+}
+
 // Constants
 const MAX_USERS: usize = 100;
 const API_VERSION: &str = "1.0.0";
@@ -16,6 +65,7 @@ struct User {
     email: String,
     active: bool,
     roles: Vec<Role>,
+    created_at: Option<i64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -31,6 +81,23 @@ enum UserError {
     NotFound(u64),
     AlreadyExists(u64),
     InvalidEmail(String),
+    Validation(String),
+    InvalidEncoding(String),
+    UnknownConversion(String),
+    Conversion(ConversionError),
+}
+
+// Carries the field and reason behind a failed type coercion during ingestion.
+#[derive(Debug)]
+struct ConversionError {
+    field: String,
+    message: String,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "field `{}`: {}", self.field, self.message)
+    }
 }
 
 impl fmt::Display for UserError {
@@ -39,10 +106,31 @@ impl fmt::Display for UserError {
             UserError::NotFound(id) => write!(f, "User {} not found", id),
             UserError::AlreadyExists(id) => write!(f, "User {} already exists", id),
             UserError::InvalidEmail(email) => write!(f, "Invalid email: {}", email),
+            UserError::Validation(why) => write!(f, "Validation failed: {}", why),
+            UserError::InvalidEncoding(why) => write!(f, "Invalid encoding: {}", why),
+            UserError::UnknownConversion(name) => write!(f, "Unknown conversion: {}", name),
+            UserError::Conversion(err) => write!(f, "Conversion failed for {}", err),
         }
     }
 }
 
+impl UserError {
+    // Whether a later attempt could plausibly succeed. Deterministic failures
+    // like `AlreadyExists` never clear on retry, so they are not transient.
+    fn is_transient(&self) -> bool {
+        !matches!(
+            self,
+            UserError::AlreadyExists(_)
+                | UserError::InvalidEmail(_)
+                | UserError::Validation(_)
+                | UserError::InvalidEncoding(_)
+                | UserError::UnknownConversion(_)
+                | UserError::Conversion(_)
+        )
+    }
+}
+
+#[cfg(feature = "std")]
 impl Error for UserError {}
 
 // Trait definition
@@ -50,49 +138,797 @@ trait UserRepository {
     fn find(&self, id: u64) -> Result<User, UserError>;
     fn save(&mut self, user: User) -> Result<(), UserError>;
     fn delete(&mut self, id: u64) -> Result<(), UserError>;
+
+    // Synchronous side of the split-client pattern: retry the insert on a
+    // transient failure instead of returning eagerly. The initial attempt plus
+    // up to `retries` re-attempts gives at most `retries + 1` saves in total.
+    // A permanent failure such as `AlreadyExists` is returned immediately; only
+    // transient errors are worth retrying. The caller gets a confirmed write
+    // (or the last error) once the attempts are exhausted.
+    fn save_and_confirm(&mut self, user: User, retries: usize) -> Result<(), UserError> {
+        let mut last = self.save(user.clone());
+        let mut attempt = 0;
+        while attempt < retries {
+            match &last {
+                Ok(()) => break,
+                Err(e) if !e.is_transient() => break,
+                Err(_) => {}
+            }
+            last = self.save(user.clone());
+            attempt += 1;
+        }
+        last
+    }
+}
+
+// Asynchronous side of the split client: `save_async` returns as soon as the
+// write is enqueued rather than waiting for confirmation.
+trait AsyncUserRepository {
+    async fn save_async(&self, user: User) -> Result<(), UserError>;
+}
+
+// Combined client exposing both the confirming sync API and the fire-and-forget
+// async API over the same backing store.
+trait Repository: UserRepository + AsyncUserRepository {}
+
+// Marks whether a cached entry matches the backing store (`Clean`) or carries
+// changes that still need to be flushed (`Dirty`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filth {
+    Clean,
+    Dirty,
 }
 
 // Implementation
 struct InMemoryUserRepository {
-    users: HashMap<u64, User>,
+    users: Arc<Mutex<HashMap<u64, User>>>,
+    // Read-through, copy-on-read overlay so that repeated lookups of the same id
+    // don't re-clone out of the backing store on every call. Held behind a
+    // `Mutex` rather than a `RefCell` so the whole repository stays `Send + Sync`
+    // and the `&self` async path can be driven on a multithreaded executor.
+    overlay: Mutex<HashMap<u64, (Filth, User)>>,
 }
 
 impl InMemoryUserRepository {
     fn new() -> Self {
         Self {
-            users: HashMap::new(),
+            users: Arc::new(Mutex::new(HashMap::new())),
+            overlay: Mutex::new(HashMap::new()),
         }
     }
-    
+
     fn with_capacity(capacity: usize) -> Self {
         Self {
-            users: HashMap::with_capacity(capacity),
+            users: Arc::new(Mutex::new(HashMap::with_capacity(capacity))),
+            overlay: Mutex::new(HashMap::with_capacity(capacity)),
+        }
+    }
+
+    // Populate the overlay from the backing store if the id is not cached yet,
+    // marking the freshly loaded entry `Clean`. Returns `NotFound` only when the
+    // id is absent from both the overlay and the backing store.
+    fn ensure_cached(&self, id: u64) -> Result<(), UserError> {
+        if self.overlay.lock().unwrap().contains_key(&id) {
+            return Ok(());
+        }
+        let user = self
+            .users
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or(UserError::NotFound(id))?;
+        self.overlay.lock().unwrap().insert(id, (Filth::Clean, user));
+        Ok(())
+    }
+
+    // Flush every `Dirty` overlay entry into the authoritative map and mark it
+    // `Clean` again.
+    fn commit(&mut self) {
+        let mut users = self.users.lock().unwrap();
+        for (id, (filth, user)) in self.overlay.lock().unwrap().iter_mut() {
+            if *filth == Filth::Dirty {
+                users.insert(*id, user.clone());
+                *filth = Filth::Clean;
+            }
+        }
+    }
+
+    // Validate `user` with `runner` before persisting. When `autofix` is set,
+    // any diagnostic that ships a fixer is applied first; the write is then
+    // rejected if an `Error`-severity diagnostic still remains.
+    fn save_validated(
+        &mut self,
+        mut user: User,
+        runner: &RuleRunner,
+        autofix: bool,
+    ) -> Result<(), UserError> {
+        let mut diagnostics = runner.run(&user);
+        if autofix {
+            for diag in &diagnostics {
+                if let Some(fixer) = &diag.fixer {
+                    user = fixer(&user);
+                }
+            }
+            diagnostics = runner.run(&user);
+        }
+        if let Some(diag) = diagnostics.iter().find(|d| d.severity == Severity::Error) {
+            return Err(UserError::Validation(diag.message.clone()));
         }
+        self.save(user)
     }
 }
 
 impl UserRepository for InMemoryUserRepository {
     fn find(&self, id: u64) -> Result<User, UserError> {
-        self.users
-            .get(&id)
-            .cloned()
-            .ok_or(UserError::NotFound(id))
+        // Seed the overlay from the backing store on the first lookup of an id,
+        // then serve the clone out of the cache so a hot id is not re-fetched
+        // from under the backing `Mutex` each time.
+        self.ensure_cached(id)?;
+        Ok(self.overlay.lock().unwrap()[&id].1.clone())
     }
-    
+
     fn save(&mut self, user: User) -> Result<(), UserError> {
-        if self.users.contains_key(&user.id) {
+        if self.overlay.lock().unwrap().contains_key(&user.id)
+            || self.users.lock().unwrap().contains_key(&user.id)
+        {
             return Err(UserError::AlreadyExists(user.id));
         }
-        
-        self.users.insert(user.id, user);
+
+        // Write into the overlay as `Dirty`; `commit` persists it later.
+        self.overlay
+            .lock()
+            .unwrap()
+            .insert(user.id, (Filth::Dirty, user));
         Ok(())
     }
-    
+
     fn delete(&mut self, id: u64) -> Result<(), UserError> {
-        self.users
-            .remove(&id)
-            .map(|_| ())
-            .ok_or(UserError::NotFound(id))
+        let in_overlay = self.overlay.lock().unwrap().remove(&id).is_some();
+        let in_backing = self.users.lock().unwrap().remove(&id).is_some();
+        if in_overlay || in_backing {
+            Ok(())
+        } else {
+            Err(UserError::NotFound(id))
+        }
+    }
+}
+
+impl AsyncUserRepository for InMemoryUserRepository {
+    // `&self` is enough because both the backing map and the overlay live behind
+    // a `Mutex`, keeping the repository `Send + Sync`. The write lands directly
+    // in the authoritative backing store so it is durable the moment the call
+    // returns — a `&self` caller cannot `commit`, so it must not be parked in the
+    // overlay. Consulting the overlay as well keeps the `AlreadyExists` invariant
+    // consistent with the synchronous `save`.
+    async fn save_async(&self, user: User) -> Result<(), UserError> {
+        if self.overlay.lock().unwrap().contains_key(&user.id) {
+            return Err(UserError::AlreadyExists(user.id));
+        }
+        let mut users = self.users.lock().unwrap();
+        if users.contains_key(&user.id) {
+            return Err(UserError::AlreadyExists(user.id));
+        }
+        users.insert(user.id, user);
+        Ok(())
+    }
+}
+
+impl Repository for InMemoryUserRepository {}
+
+// Binary serialization
+//
+// Each `User` is encoded behind a small compatibility header so that records
+// written by an older build can still be recognized (and rejected cleanly) by a
+// newer one. The header is a fixed-length tag plus a `u16` format version,
+// mirroring the `NetworkVersion` handshake used elsewhere.
+
+// Fixed-length tag identifying the record format, padded with NULs.
+const RECORD_TAG: [u8; 8] = *b"USERREC\0";
+// Current on-disk format version. Bumped to 2 when the optional `created_at`
+// timestamp was added to the record layout.
+const FORMAT_VERSION: u16 = 2;
+
+// Role discriminants, kept stable across versions.
+const ROLE_ADMIN: u8 = 0;
+const ROLE_USER: u8 = 1;
+const ROLE_GUEST: u8 = 2;
+
+// On-disk fields that were introduced over time. Each variant records the
+// format version it first appeared in, so a reader can decode exactly the
+// fields its input is known to carry and skip the rest — the per-feature
+// negotiation the `supports_nack_with_list_and_motive` reference performs for a
+// wire protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Feature {
+    // The length-prefixed role sequence; present since the first format.
+    Roles,
+    // The optional `created_at` timestamp, added in format version 2.
+    CreatedAt,
+}
+
+impl Feature {
+    // The first format version in which this feature is present.
+    fn since(self) -> u16 {
+        match self {
+            Feature::Roles => 1,
+            Feature::CreatedAt => 2,
+        }
+    }
+}
+
+// Whether a record written at `version` carries `feature`. Callers gate an
+// optionally-added field on this so newer readers stay backward-compatible with
+// records that predate the field.
+fn supports_feature(version: u16, feature: Feature) -> bool {
+    version >= feature.since()
+}
+
+// Whether `version` is one this build knows how to decode at all. Versions below
+// the first release or beyond the current one are rejected outright, so a future
+// layout is never misread with an older field order.
+fn is_known_version(version: u16) -> bool {
+    (1..=FORMAT_VERSION).contains(&version)
+}
+
+// Append-only writer over an owned byte buffer.
+struct BinWriter {
+    buf: Vec<u8>,
+}
+
+impl BinWriter {
+    fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+
+    fn write_str(&mut self, s: &str) {
+        self.buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+        self.buf.extend_from_slice(s.as_bytes());
+    }
+
+    fn write_user(&mut self, user: &User) {
+        self.buf.extend_from_slice(&RECORD_TAG);
+        self.buf.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        self.write_str(&user.name);
+        self.write_str(&user.email);
+        self.buf.extend_from_slice(&user.id.to_le_bytes());
+        self.buf.push(user.active as u8);
+        self.buf.extend_from_slice(&(user.roles.len() as u32).to_le_bytes());
+        for role in &user.roles {
+            self.buf.push(match role {
+                Role::Admin => ROLE_ADMIN,
+                Role::User => ROLE_USER,
+                Role::Guest => ROLE_GUEST,
+            });
+        }
+        // `created_at` is encoded as a one-byte presence tag optionally followed
+        // by a little-endian `i64`, so `None` stays compact. Gated on the feature
+        // so the writer and reader agree on when the field is on the wire.
+        if supports_feature(FORMAT_VERSION, Feature::CreatedAt) {
+            match user.created_at {
+                Some(ts) => {
+                    self.buf.push(1);
+                    self.buf.extend_from_slice(&ts.to_le_bytes());
+                }
+                None => self.buf.push(0),
+            }
+        }
+    }
+}
+
+// Cursor-style reader over a borrowed byte slice.
+struct BinReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BinReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], UserError> {
+        if self.pos + n > self.buf.len() {
+            return Err(UserError::InvalidEncoding("unexpected end of input".into()));
+        }
+        let slice = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, UserError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, UserError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, UserError> {
+        let bytes = self.take(8)?;
+        let mut arr = [0u8; 8];
+        arr.copy_from_slice(bytes);
+        Ok(u64::from_le_bytes(arr))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, UserError> {
+        let bytes = self.take(8)?;
+        let mut arr = [0u8; 8];
+        arr.copy_from_slice(bytes);
+        Ok(i64::from_le_bytes(arr))
+    }
+
+    fn read_str(&mut self) -> Result<String, UserError> {
+        let len = self.read_u32()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| UserError::InvalidEncoding("name/email is not valid utf-8".into()))
+    }
+
+    fn read_user(&mut self) -> Result<User, UserError> {
+        let tag = self.take(RECORD_TAG.len())?;
+        if tag != RECORD_TAG {
+            return Err(UserError::InvalidEncoding("unrecognized record tag".into()));
+        }
+        let version = self.read_u16()?;
+        if !is_known_version(version) {
+            return Err(UserError::InvalidEncoding(format!(
+                "unsupported format version {}",
+                version
+            )));
+        }
+        let name = self.read_str()?;
+        let email = self.read_str()?;
+        let id = self.read_u64()?;
+        let active = self.take(1)?[0] != 0;
+        let mut roles = Vec::new();
+        if supports_feature(version, Feature::Roles) {
+            let count = self.read_u32()? as usize;
+            roles.reserve(count);
+            for _ in 0..count {
+                let byte = self.take(1)?[0];
+                roles.push(match byte {
+                    ROLE_ADMIN => Role::Admin,
+                    ROLE_USER => Role::User,
+                    ROLE_GUEST => Role::Guest,
+                    other => {
+                        return Err(UserError::InvalidEncoding(format!(
+                            "unknown role discriminant {}",
+                            other
+                        )))
+                    }
+                });
+            }
+        }
+        // Records written before version 2 have no `created_at` on the wire;
+        // those default to `None` without consuming any bytes.
+        let created_at = if supports_feature(version, Feature::CreatedAt) {
+            match self.take(1)?[0] {
+                0 => None,
+                1 => Some(self.read_i64()?),
+                other => {
+                    return Err(UserError::InvalidEncoding(format!(
+                        "invalid created_at tag {}",
+                        other
+                    )))
+                }
+            }
+        } else {
+            None
+        };
+        Ok(User {
+            id,
+            name,
+            email,
+            active,
+            roles,
+            created_at,
+        })
+    }
+}
+
+// Event sourcing
+//
+// Instead of mutating the map in place, every change is recorded as a
+// timestamped `Operation` in an append-only log. A full copy of the state is
+// snapshotted every `KEEP_STATE_EVERY` operations so that `sync()` can rebuild
+// current state from the latest checkpoint plus the tail of the log, the way a
+// `Bayou`-style store does. This keeps crash-recoverable history and lets a
+// caller reconstruct past states.
+
+// How many operations to apply between full-state checkpoints.
+const KEEP_STATE_EVERY: usize = 64;
+
+#[derive(Debug, Clone)]
+enum Operation {
+    Save(User),
+    Delete(u64),
+}
+
+// An operation paired with the logical time at which it was recorded.
+//
+// `timestamp` comes from `EventSourcedUserRepository::tick`, a strictly
+// increasing logical clock, so every recorded operation carries a distinct
+// timestamp. Two operations therefore can never share a timestamp, which is why
+// replay needs no tie-break on operation kind: the timestamp order is already a
+// total order that matches append order.
+#[derive(Debug, Clone)]
+struct TimestampedOp {
+    timestamp: u64,
+    op: Operation,
+}
+
+// A full snapshot of state as of a given timestamp.
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    timestamp: u64,
+    state: HashMap<u64, User>,
+}
+
+struct EventSourcedUserRepository {
+    live: HashMap<u64, User>,
+    log: Vec<TimestampedOp>,
+    checkpoints: Vec<Checkpoint>,
+    // Strictly increasing logical clock; see `TimestampedOp` for why its
+    // uniqueness makes an operation-kind tie-break unnecessary during replay.
+    clock: u64,
+}
+
+impl EventSourcedUserRepository {
+    fn new() -> Self {
+        Self {
+            live: HashMap::new(),
+            log: Vec::new(),
+            checkpoints: vec![Checkpoint {
+                timestamp: 0,
+                state: HashMap::new(),
+            }],
+            clock: 0,
+        }
+    }
+
+    // Monotonically increasing logical clock backing operation timestamps. Each
+    // `tick` returns a fresh, unique value, so operations carry a total order
+    // that already matches their append order.
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    // Apply a single operation to an arbitrary state map (used both for the live
+    // state and during replay).
+    fn apply_op(state: &mut HashMap<u64, User>, op: &Operation) {
+        match op {
+            Operation::Save(user) => {
+                state.insert(user.id, user.clone());
+            }
+            Operation::Delete(id) => {
+                state.remove(id);
+            }
+        }
+    }
+
+    // Append an operation, update the live state, and checkpoint periodically.
+    fn append(&mut self, op: Operation) {
+        let timestamp = self.tick();
+        Self::apply_op(&mut self.live, &op);
+        self.log.push(TimestampedOp { timestamp, op });
+        if self.log.len().is_multiple_of(KEEP_STATE_EVERY) {
+            self.checkpoints.push(Checkpoint {
+                timestamp,
+                state: self.live.clone(),
+            });
+        }
+    }
+
+    // Rebuild live state from the latest checkpoint plus every later operation,
+    // applied in timestamp order. Timestamps are unique and increase with append
+    // order, so iterating the log as stored already yields the correct order.
+    fn sync(&mut self) {
+        let checkpoint = self
+            .checkpoints
+            .last()
+            .expect("at least the genesis checkpoint is always present");
+        let mut state = checkpoint.state.clone();
+        let since = checkpoint.timestamp;
+
+        for entry in self.log.iter().filter(|o| o.timestamp > since) {
+            Self::apply_op(&mut state, &entry.op);
+        }
+        self.live = state;
+    }
+
+    fn find(&self, id: u64) -> Result<User, UserError> {
+        self.live.get(&id).cloned().ok_or(UserError::NotFound(id))
+    }
+
+    fn save(&mut self, user: User) -> Result<(), UserError> {
+        self.append(Operation::Save(user));
+        Ok(())
+    }
+
+    fn delete(&mut self, id: u64) -> Result<(), UserError> {
+        if !self.live.contains_key(&id) {
+            return Err(UserError::NotFound(id));
+        }
+        self.append(Operation::Delete(id));
+        Ok(())
+    }
+}
+
+// Conversions
+//
+// Users frequently arrive as untyped text rows (CSV columns, log fields). A
+// `Conversion` names how a raw string should be coerced, and is itself parsed
+// from a short type name so ingestion can be configured declaratively.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Conversion {
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    TimestampFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = UserError;
+
+    fn from_str(s: &str) -> Result<Self, UserError> {
+        match s {
+            "asis" | "string" => Ok(Conversion::Bytes),
+            "int" | "integer" => Ok(Conversion::Integer),
+            "float" => Ok(Conversion::Float),
+            "bool" | "boolean" => Ok(Conversion::Boolean),
+            "timestamp" => Ok(Conversion::Timestamp),
+            other => match other.strip_prefix("timestamp|") {
+                Some(fmt) => Ok(Conversion::TimestampFmt(fmt.to_string())),
+                None => Err(UserError::UnknownConversion(other.to_string())),
+            },
+        }
+    }
+}
+
+// A value after coercion, before it is routed to a `User` field.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldValue {
+    Bytes(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Timestamp(i64),
+}
+
+impl Conversion {
+    // Coerce `raw` according to this conversion, tagging any failure with the
+    // originating `field`.
+    fn apply(&self, field: &str, raw: &str) -> Result<FieldValue, UserError> {
+        let fail = |message: String| {
+            UserError::Conversion(ConversionError {
+                field: field.to_string(),
+                message,
+            })
+        };
+        match self {
+            Conversion::Bytes => Ok(FieldValue::Bytes(raw.to_string())),
+            Conversion::Integer => raw
+                .trim()
+                .parse::<i64>()
+                .map(FieldValue::Integer)
+                .map_err(|e: ParseIntError| fail(e.to_string())),
+            Conversion::Float => raw
+                .trim()
+                .parse::<f64>()
+                .map(FieldValue::Float)
+                .map_err(|e: ParseFloatError| fail(e.to_string())),
+            Conversion::Boolean => match raw.trim() {
+                "true" | "1" => Ok(FieldValue::Boolean(true)),
+                "false" | "0" => Ok(FieldValue::Boolean(false)),
+                other => Err(fail(format!("not a boolean: {}", other))),
+            },
+            Conversion::Timestamp => raw
+                .trim()
+                .parse::<i64>()
+                .map(FieldValue::Timestamp)
+                .map_err(|e: ParseIntError| fail(e.to_string())),
+            Conversion::TimestampFmt(fmt) => NaiveDateTime::parse_from_str(raw.trim(), fmt)
+                .map(|dt| FieldValue::Timestamp(dt.and_utc().timestamp()))
+                .map_err(|e| fail(e.to_string())),
+        }
+    }
+}
+
+impl User {
+    // Build a `User` from named text fields, each paired with the conversion to
+    // apply. Unknown field/conversion pairings and parse failures surface as a
+    // typed `UserError`.
+    fn from_fields(fields: &HashMap<String, (String, Conversion)>) -> Result<User, UserError> {
+        let mut user = User {
+            id: 0,
+            name: String::new(),
+            email: String::new(),
+            active: false,
+            roles: Vec::new(),
+            created_at: None,
+        };
+        for (field, (raw, conversion)) in fields {
+            let value = conversion.apply(field, raw)?;
+            match (field.as_str(), value) {
+                ("id", FieldValue::Integer(n)) => user.id = n as u64,
+                ("name", FieldValue::Bytes(s)) => user.name = s,
+                ("email", FieldValue::Bytes(s)) => user.email = s,
+                ("active", FieldValue::Boolean(b)) => user.active = b,
+                ("created_at", FieldValue::Timestamp(t)) => user.created_at = Some(t),
+                (other, _) => {
+                    return Err(UserError::Conversion(ConversionError {
+                        field: other.to_string(),
+                        message: "unsupported field or conversion for this field".to_string(),
+                    }))
+                }
+            }
+        }
+        Ok(user)
+    }
+}
+
+// Validation
+//
+// A small lint-style engine: each `Rule` inspects a `User` and emits zero or
+// more `Diagnostic`s. A diagnostic carries a `Severity`, a human-readable
+// message, and an optional `Fixer` that yields a corrected `User`. Rules are
+// `Send + Sync` so a `RuleRunner` can fan a batch out across threads.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Error,
+    Warning,
+}
+
+// A closure that rewrites a user into a corrected form.
+type Fixer = Box<dyn Fn(&User) -> User + Send + Sync>;
+
+struct Diagnostic {
+    severity: Severity,
+    message: String,
+    fixer: Option<Fixer>,
+}
+
+impl Diagnostic {
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            fixer: None,
+        }
+    }
+
+    fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            fixer: None,
+        }
+    }
+
+    fn with_fixer(mut self, fixer: Fixer) -> Self {
+        self.fixer = Some(fixer);
+        self
+    }
+}
+
+trait Rule: Send + Sync {
+    fn check(&self, user: &User) -> Vec<Diagnostic>;
+}
+
+// `name` must not be empty; the fixer substitutes a placeholder derived from the
+// id so ingestion can continue.
+struct NonEmptyName;
+
+impl Rule for NonEmptyName {
+    fn check(&self, user: &User) -> Vec<Diagnostic> {
+        if user.name.trim().is_empty() {
+            vec![Diagnostic::error("name must not be empty")
+                .with_fixer(Box::new(|u: &User| User {
+                    name: format!("user-{}", u.id),
+                    ..u.clone()
+                }))]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// RFC-ish shape check: a non-empty local part, an `@`, and a dotted domain.
+struct ValidEmail;
+
+impl Rule for ValidEmail {
+    fn check(&self, user: &User) -> Vec<Diagnostic> {
+        let ok = match user.email.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty() && domain.contains('.') && !domain.starts_with('.')
+            }
+            None => false,
+        };
+        if ok {
+            Vec::new()
+        } else {
+            vec![Diagnostic::error(format!("invalid email: {}", user.email))]
+        }
+    }
+}
+
+// An inactive account that still carries `Role::Admin` is suspicious; the fixer
+// strips the admin grant.
+struct InactiveAdmin;
+
+impl Rule for InactiveAdmin {
+    fn check(&self, user: &User) -> Vec<Diagnostic> {
+        if !user.active && user.roles.contains(&Role::Admin) {
+            vec![
+                Diagnostic::warning("inactive user still holds Role::Admin").with_fixer(Box::new(
+                    |u: &User| User {
+                        roles: u.roles.iter().filter(|r| **r != Role::Admin).cloned().collect(),
+                        ..u.clone()
+                    },
+                )),
+            ]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+struct RuleRunner {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl RuleRunner {
+    fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    // A runner pre-loaded with the built-in rule set.
+    fn with_builtins() -> Self {
+        let mut runner = Self::new();
+        runner.add(Box::new(NonEmptyName));
+        runner.add(Box::new(ValidEmail));
+        runner.add(Box::new(InactiveAdmin));
+        runner
+    }
+
+    fn add(&mut self, rule: Box<dyn Rule>) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    // Run every rule against a single user.
+    fn run(&self, user: &User) -> Vec<Diagnostic> {
+        self.rules.iter().flat_map(|rule| rule.check(user)).collect()
+    }
+
+    // Run the rules over a batch, one user per worker thread.
+    #[cfg(feature = "std")]
+    fn run_batch(&self, users: &[User]) -> Vec<Vec<Diagnostic>> {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = users
+                .iter()
+                .map(|user| scope.spawn(move || self.run(user)))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        })
+    }
+
+    // Without `std` there are no threads, so fall back to a sequential sweep.
+    #[cfg(not(feature = "std"))]
+    fn run_batch(&self, users: &[User]) -> Vec<Vec<Diagnostic>> {
+        users.iter().map(|user| self.run(user)).collect()
     }
 }
 
@@ -140,6 +976,7 @@ fn greet_with_age(name: &str, age: Option<u32>) -> String {
 }
 
 // Pattern matching
+#[cfg(feature = "std")]
 fn process_result(result: Result<User, UserError>) {
     match result {
         Ok(user) => println!("Found user: {:?}", user),
@@ -149,6 +986,7 @@ fn process_result(result: Result<User, UserError>) {
 }
 
 // If let
+#[allow(clippy::manual_map)]
 fn get_first_element(vec: Vec<i32>) -> Option<i32> {
     if let Some(first) = vec.first() {
         Some(*first)
@@ -165,6 +1003,12 @@ where
     op(x, y)
 }
 
+// The demo driver exercises std-only facilities (threads, filesystem,
+// `println!`), so it is compiled only when the `std` feature is enabled. It is a
+// deliberate grab-bag of language constructs, many unused, so the usual unused /
+// style lints are muted here rather than distorting the illustrations.
+#[cfg(feature = "std")]
+#[allow(unused, unused_must_use, clippy::all)]
 fn main() {
     // Variables
     let immutable = 42;
@@ -225,7 +1069,7 @@ fn main() {
     }
     
     // Closures
-    let add = |a, b| a + b;
+    let add = |a: i32, b: i32| a + b;
     let multiply = |a: i32, b: i32| -> i32 { a * b };
     
     // Higher-order functions
@@ -309,38 +1153,273 @@ fn main() {
         x: i32,
         y: i32,
     }
-    
-    // Tests
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        
-        #[test]
-        fn test_greet() {
-            assert_eq!(greet("World"), "Hello, World!");
-        }
-        
-        #[test]
-        fn test_user_repository() {
-            let mut repo = InMemoryUserRepository::new();
-            let user = User {
-                id: 1,
-                name: String::from("John"),
-                email: String::from("john@example.com"),
-                active: true,
-                roles: vec![Role::User],
-            };
-            
-            assert!(repo.save(user.clone()).is_ok());
-            assert_eq!(repo.find(1).unwrap(), user);
-        }
-    }
 }
 
+#[cfg(feature = "std")]
+#[allow(clippy::ptr_arg)]
 fn calculate_length(s: &String) -> usize {
     s.len()
 }
 
+#[cfg(feature = "std")]
 fn change(s: &mut String) {
     s.push_str(", world");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A plain, valid user the tests can clone and tweak.
+    fn sample_user(id: u64) -> User {
+        User {
+            id,
+            name: String::from("John"),
+            email: String::from("john@example.com"),
+            active: true,
+            roles: vec![Role::User],
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn save_and_confirm_succeeds_on_first_try() {
+        let mut repo = InMemoryUserRepository::new();
+        assert!(repo.save_and_confirm(sample_user(1), 3).is_ok());
+        assert_eq!(repo.find(1).unwrap(), sample_user(1));
+    }
+
+    #[test]
+    fn already_exists_is_not_transient() {
+        assert!(!UserError::AlreadyExists(1).is_transient());
+        assert!(UserError::NotFound(1).is_transient());
+    }
+
+    #[test]
+    fn save_and_confirm_does_not_loop_on_permanent_error() {
+        let mut repo = InMemoryUserRepository::new();
+        repo.save(sample_user(1)).unwrap();
+        // A duplicate id fails permanently; retries must not mask it.
+        let err = repo.save_and_confirm(sample_user(1), 5).unwrap_err();
+        assert!(matches!(err, UserError::AlreadyExists(1)));
+
+        // A store that always reports a duplicate must see exactly one attempt.
+        struct AlwaysDuplicate {
+            saves: usize,
+        }
+        impl UserRepository for AlwaysDuplicate {
+            fn find(&self, id: u64) -> Result<User, UserError> {
+                Err(UserError::NotFound(id))
+            }
+            fn save(&mut self, user: User) -> Result<(), UserError> {
+                self.saves += 1;
+                Err(UserError::AlreadyExists(user.id))
+            }
+            fn delete(&mut self, id: u64) -> Result<(), UserError> {
+                Err(UserError::NotFound(id))
+            }
+        }
+        let mut stub = AlwaysDuplicate { saves: 0 };
+        assert!(stub.save_and_confirm(sample_user(1), 5).is_err());
+        assert_eq!(stub.saves, 1);
+    }
+
+    #[test]
+    fn user_survives_a_bin_round_trip() {
+        let mut user = sample_user(7);
+        user.roles = vec![Role::Admin, Role::Guest];
+        let mut writer = BinWriter::new();
+        writer.write_user(&user);
+        let bytes = writer.into_bytes();
+        let decoded = BinReader::new(&bytes).read_user().unwrap();
+        assert_eq!(decoded, user);
+    }
+
+    #[test]
+    fn future_format_version_is_rejected() {
+        let mut writer = BinWriter::new();
+        writer.write_user(&sample_user(1));
+        let mut bytes = writer.into_bytes();
+        // The version is the u16 immediately after the fixed-length tag.
+        bytes[RECORD_TAG.len()] = FORMAT_VERSION as u8 + 1;
+        let err = BinReader::new(&bytes).read_user().unwrap_err();
+        assert!(matches!(err, UserError::InvalidEncoding(_)));
+    }
+
+    #[test]
+    fn unknown_role_byte_is_rejected() {
+        let mut user = sample_user(1);
+        user.name.clear();
+        user.email.clear();
+        user.roles = vec![Role::User];
+        let mut writer = BinWriter::new();
+        writer.write_user(&user);
+        let mut bytes = writer.into_bytes();
+        // With empty strings the single role discriminant sits at a fixed offset:
+        // tag(8) + version(2) + name_len(4) + email_len(4) + id(8) + active(1)
+        // + role_count(4).
+        let role_offset = RECORD_TAG.len() + 2 + 4 + 4 + 8 + 1 + 4;
+        bytes[role_offset] = 9;
+        let err = BinReader::new(&bytes).read_user().unwrap_err();
+        assert!(matches!(err, UserError::InvalidEncoding(_)));
+    }
+
+    #[test]
+    fn replay_rebuilds_state_past_a_checkpoint() {
+        let mut repo = EventSourcedUserRepository::new();
+        // Enough operations to cross at least one checkpoint boundary.
+        let total = KEEP_STATE_EVERY + 6;
+        for id in 0..total as u64 {
+            repo.save(sample_user(id)).unwrap();
+        }
+        repo.delete(3).unwrap();
+        assert!(repo.checkpoints.len() >= 2, "a checkpoint should have been taken");
+
+        // Wipe live state and rebuild it purely from checkpoint + op log.
+        repo.live = HashMap::new();
+        repo.sync();
+
+        assert!(repo.find(3).is_err());
+        assert_eq!(repo.find(0).unwrap(), sample_user(0));
+        assert_eq!(repo.find((total - 1) as u64).unwrap().id, (total - 1) as u64);
+    }
+
+    #[test]
+    fn deleting_absent_id_reports_not_found() {
+        let mut repo = EventSourcedUserRepository::new();
+        assert!(matches!(repo.delete(42), Err(UserError::NotFound(42))));
+    }
+
+    #[test]
+    fn validation_rejects_error_diagnostics() {
+        let runner = RuleRunner::with_builtins();
+        let mut repo = InMemoryUserRepository::new();
+        let mut user = sample_user(1);
+        user.name = String::new();
+        let err = repo.save_validated(user, &runner, false).unwrap_err();
+        match err {
+            UserError::Validation(msg) => assert!(msg.contains("name")),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn autofix_repairs_then_saves() {
+        let runner = RuleRunner::with_builtins();
+        let mut repo = InMemoryUserRepository::new();
+        let mut user = sample_user(1);
+        user.name = String::new();
+        repo.save_validated(user, &runner, true).unwrap();
+        assert_eq!(repo.find(1).unwrap().name, "user-1");
+    }
+
+    #[test]
+    fn warnings_do_not_block_a_save() {
+        let runner = RuleRunner::with_builtins();
+        let mut repo = InMemoryUserRepository::new();
+        let mut user = sample_user(1);
+        user.active = false;
+        user.roles = vec![Role::Admin];
+        // The inactive-admin rule only warns, so the write still goes through.
+        repo.save_validated(user, &runner, false).unwrap();
+        assert!(repo.find(1).is_ok());
+    }
+
+    #[test]
+    fn conversion_names_resolve() {
+        assert_eq!("int".parse::<Conversion>().unwrap(), Conversion::Integer);
+        assert_eq!("boolean".parse::<Conversion>().unwrap(), Conversion::Boolean);
+        assert_eq!(
+            "timestamp|%Y-%m-%d".parse::<Conversion>().unwrap(),
+            Conversion::TimestampFmt(String::from("%Y-%m-%d"))
+        );
+    }
+
+    #[test]
+    fn unknown_conversion_name_is_reported() {
+        let err = "widget".parse::<Conversion>().unwrap_err();
+        assert!(matches!(err, UserError::UnknownConversion(name) if name == "widget"));
+    }
+
+    #[test]
+    fn from_fields_applies_each_conversion() {
+        let mut fields = HashMap::new();
+        fields.insert("id".to_string(), ("42".to_string(), Conversion::Integer));
+        fields.insert("name".to_string(), ("Ada".to_string(), Conversion::Bytes));
+        fields.insert("active".to_string(), ("true".to_string(), Conversion::Boolean));
+        let user = User::from_fields(&fields).unwrap();
+        assert_eq!(user.id, 42);
+        assert_eq!(user.name, "Ada");
+        assert!(user.active);
+    }
+
+    #[test]
+    fn from_fields_surfaces_parse_failures() {
+        let mut fields = HashMap::new();
+        fields.insert("id".to_string(), ("nope".to_string(), Conversion::Integer));
+        let err = User::from_fields(&fields).unwrap_err();
+        assert!(matches!(err, UserError::Conversion(_)));
+    }
+
+    #[test]
+    fn created_at_survives_a_bin_round_trip() {
+        let mut user = sample_user(1);
+        user.created_at = Some(1_700_000_000);
+        let mut writer = BinWriter::new();
+        writer.write_user(&user);
+        let bytes = writer.into_bytes();
+        let decoded = BinReader::new(&bytes).read_user().unwrap();
+        assert_eq!(decoded.created_at, Some(1_700_000_000));
+    }
+
+    // Minimal executor for the fire-and-forget async path: `save_async` never
+    // yields, so a single poll with a no-op waker always drives it to completion.
+    fn block_on<F: core::future::Future>(future: F) -> F::Output {
+        use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+        fn noop(_: *const ()) {}
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(core::ptr::null(), &VTABLE)
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+        let waker = unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        let mut future = future;
+        // Safety: `future` stays on the stack for the whole loop and is not moved
+        // after being pinned.
+        let mut pinned = unsafe { core::pin::Pin::new_unchecked(&mut future) };
+        loop {
+            if let Poll::Ready(value) = pinned.as_mut().poll(&mut cx) {
+                return value;
+            }
+        }
+    }
+
+    #[test]
+    fn commit_flushes_dirty_overlay_entries() {
+        let mut repo = InMemoryUserRepository::new();
+        repo.save(sample_user(1)).unwrap();
+        // Before commit the write lives only in the overlay, not the backing map.
+        assert!(!repo.users.lock().unwrap().contains_key(&1));
+        assert_eq!(repo.find(1).unwrap(), sample_user(1));
+        repo.commit();
+        assert!(repo.users.lock().unwrap().contains_key(&1));
+    }
+
+    #[test]
+    fn async_save_is_durable_without_commit() {
+        let repo = InMemoryUserRepository::new();
+        block_on(repo.save_async(sample_user(1))).unwrap();
+        // The async path writes straight to the authoritative store.
+        assert!(repo.users.lock().unwrap().contains_key(&1));
+    }
+
+    #[test]
+    fn already_exists_holds_across_sync_and_async() {
+        let mut repo = InMemoryUserRepository::new();
+        repo.save(sample_user(1)).unwrap();
+        let err = block_on(repo.save_async(sample_user(1))).unwrap_err();
+        assert!(matches!(err, UserError::AlreadyExists(1)));
+    }
+}